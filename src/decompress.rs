@@ -0,0 +1,288 @@
+/*******************************************************************************
+ * Copyright (c) 2024 Nicholas LaRoche <nicholas.louis.laroche@outlook.com>
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Eclipse Public License v. 2.0 which is available at
+ * http://www.eclipse.org/legal/epl-2.0.
+ *
+ * SPDX-License-Identifier: EPL-2.0
+ *******************************************************************************/
+//! Decompressor registry mapping archive extensions to external command
+//! templates, so that the crate is not permanently wired to the `7z` binary.
+//!
+//! This mirrors the approach ripgrep takes in its own `decompress.rs`: a
+//! small table of (suffix, command template) entries, matched by longest
+//! suffix so that `.tar.gz` is preferred over a bare `.gz` rule. Built-in
+//! entries cover the common archive formats; users can add or override
+//! entries from the command line with `--decompressor ".ext:program args"`.
+
+use std::io;
+use std::process::Command;
+
+use crate::process::{self, StreamedOutput};
+
+/// A single registered decompressor: the extension it claims, and the
+/// argument templates used to list or extract an archive of that kind.
+///
+/// Templates are whitespace-split argument lists where the tokens `{file}`,
+/// `{out}` and `{password}` are substituted with the archive path, the
+/// output directory, and the password flag (empty when no password is set,
+/// built from `password_flag_prefix` otherwise) respectively.
+#[derive(Clone, Debug)]
+pub struct Decompressor {
+    pub suffix: String,
+    pub program: String,
+    pub list_args: Vec<String>,
+    pub list_structured_args: Vec<String>,
+    pub extract_all_args: Vec<String>,
+    pub extract_one_args: Vec<String>,
+    pub password_flag_prefix: String,
+    /// Whether `list_args` already emits one bare member name per line (e.g.
+    /// `tar tzf`, `unzip -Z1`, `unrar lb`) with no surrounding columns to
+    /// strip. Decompressors with no `list_structured_args` fall back to
+    /// parsing `list_args`' output directly, and that fallback needs to know
+    /// whether a line already *is* the name or needs column-stripping tuned
+    /// to a specific tool's layout (only `7z`'s default listing today).
+    pub list_is_bare: bool,
+}
+
+impl Decompressor {
+    fn new(
+        suffix: &str,
+        program: &str,
+        password_flag_prefix: &str,
+        list_args: &[&str],
+        list_structured_args: &[&str],
+        extract_all_args: &[&str],
+        extract_one_args: &[&str],
+        list_is_bare: bool,
+    ) -> Self {
+        Decompressor {
+            suffix: suffix.to_string(),
+            program: program.to_string(),
+            list_args: list_args.iter().map(|s| s.to_string()).collect(),
+            list_structured_args: list_structured_args.iter().map(|s| s.to_string()).collect(),
+            extract_all_args: extract_all_args.iter().map(|s| s.to_string()).collect(),
+            extract_one_args: extract_one_args.iter().map(|s| s.to_string()).collect(),
+            password_flag_prefix: password_flag_prefix.to_string(),
+            list_is_bare,
+        }
+    }
+
+    /// Builds the command used to list the contents of `file`.
+    pub fn list_command(&self, file: &str, password: &str) -> Command {
+        self.build_command(&self.list_args, file, "", "", password)
+    }
+
+    /// Builds the command used to produce a structured, per-entry listing
+    /// of `file` (e.g. `7z l -slt`), or `None` if this decompressor has no
+    /// structured listing form — `--manifest` can't be built from it.
+    pub fn list_structured_command(&self, file: &str, password: &str) -> Option<Command> {
+        if self.list_structured_args.is_empty() {
+            return None;
+        }
+        Some(self.build_command(&self.list_structured_args, file, "", "", password))
+    }
+
+    /// Builds the command used to extract every entry of `file` into
+    /// `output_directory`.
+    pub fn extract_all_command(&self, file: &str, output_directory: &str, password: &str) -> Command {
+        self.build_command(&self.extract_all_args, file, output_directory, "", password)
+    }
+
+    /// Builds the command used to extract a single `member` of `file` into
+    /// `output_directory`.
+    pub fn extract_one_command(
+        &self,
+        file: &str,
+        member: &str,
+        output_directory: &str,
+        password: &str,
+    ) -> Command {
+        self.build_command(&self.extract_one_args, file, output_directory, member, password)
+    }
+
+    fn build_command(
+        &self,
+        args: &[String],
+        file: &str,
+        output_directory: &str,
+        member: &str,
+        password: &str,
+    ) -> Command {
+        let password_arg = if password.is_empty() {
+            String::new()
+        } else {
+            format!("{}{}", self.password_flag_prefix, password)
+        };
+
+        let mut command = Command::new(&self.program);
+        for arg in args {
+            let substituted = arg
+                .replace("{file}", file)
+                .replace("{out}", output_directory)
+                .replace("{member}", member)
+                .replace("{password}", &password_arg);
+
+            if substituted.is_empty() {
+                continue;
+            }
+            command.arg(substituted);
+        }
+        command
+    }
+}
+
+/// A registry of decompressors keyed by extension, consulted by
+/// `try_to_list_files`/`try_to_extract_file` to choose which external
+/// program handles a given archive.
+#[derive(Clone, Debug)]
+pub struct DecompressorRegistry {
+    entries: Vec<Decompressor>,
+}
+
+impl Default for DecompressorRegistry {
+    fn default() -> Self {
+        DecompressorRegistry {
+            entries: vec![
+                Decompressor::new(
+                    ".7z",
+                    "7z",
+                    "-p",
+                    &["l", "-r", "-ba", "{password}", "{file}"],
+                    &["l", "-slt", "-ba", "{password}", "{file}"],
+                    &["x", "{file}", "{password}", "-y", "-o{out}"],
+                    &["e", "{file}", "{member}", "{password}", "-y", "-o{out}"],
+                    false,
+                ),
+                Decompressor::new(
+                    ".tar.gz",
+                    "tar",
+                    "-p",
+                    // `tar tzf` already prints one bare path per line.
+                    &["tzf", "{file}"],
+                    &[],
+                    &["xzf", "{file}", "-C", "{out}"],
+                    &["xzf", "{file}", "-C", "{out}", "{member}"],
+                    true,
+                ),
+                Decompressor::new(
+                    ".tgz",
+                    "tar",
+                    "-p",
+                    &["tzf", "{file}"],
+                    &[],
+                    &["xzf", "{file}", "-C", "{out}"],
+                    &["xzf", "{file}", "-C", "{out}", "{member}"],
+                    true,
+                ),
+                Decompressor::new(
+                    ".zip",
+                    "unzip",
+                    // unzip's `-p` means "extract to stdout", not "password" -
+                    // the password flag is capital `-P`.
+                    "-P",
+                    // `-Z1` (zipinfo mode, short format) prints one bare name
+                    // per line instead of `-l`'s headered, columned table.
+                    &["-Z1", "{file}"],
+                    &[],
+                    &["-o", "{password}", "{file}", "-d", "{out}"],
+                    &["-o", "{password}", "{file}", "{member}", "-d", "{out}"],
+                    true,
+                ),
+                Decompressor::new(
+                    ".rar",
+                    "unrar",
+                    "-p",
+                    // `lb` ("list bare") prints one bare name per line
+                    // instead of `l`'s headered, columned table.
+                    &["lb", "{file}"],
+                    &[],
+                    &["x", "{password}", "-y", "{file}", "{out}/"],
+                    &["x", "{password}", "-y", "{file}", "{member}", "{out}/"],
+                    true,
+                ),
+            ],
+        }
+    }
+}
+
+impl DecompressorRegistry {
+    /// Registers (or overrides) an entry from a `--decompressor` CLI value
+    /// of the form `.ext:program arg1 arg2 -o{out}`.
+    pub fn register(&mut self, spec: &str) -> Result<(), String> {
+        let (suffix, command) = spec.split_once(':').ok_or_else(|| {
+            format!("invalid --decompressor value {:?}, expected \".ext:program args\"", spec)
+        })?;
+
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| format!("invalid --decompressor value {:?}, missing program", spec))?
+            .to_string();
+        let args: Vec<String> = parts.map(|s| s.to_string()).collect();
+
+        let entry = Decompressor {
+            suffix: suffix.to_string(),
+            program,
+            list_args: args.clone(),
+            // Custom templates have no structured-listing equivalent of
+            // `7z l -slt`, so `--manifest` records a failure for archives
+            // handled by them instead of listing their entries.
+            list_structured_args: Vec::new(),
+            extract_all_args: args.clone(),
+            extract_one_args: {
+                let mut one = args.clone();
+                one.push("{member}".to_string());
+                one
+            },
+            // Custom tools can't declare their own password-flag spelling
+            // from the command line yet, so this matches the most common
+            // convention (`7z`, `tar`, `unrar`); users whose tool needs
+            // something else should bake `{password}` out of their template
+            // and pass the flag as a literal argument instead.
+            password_flag_prefix: "-p".to_string(),
+            // Same reasoning as `list_structured_args`: there's no way to
+            // know a custom tool's column layout, so its listing is taken
+            // as bare lines rather than guessing at (and panicking on) a
+            // fixed offset tuned for a specific tool.
+            list_is_bare: true,
+        };
+
+        self.entries.retain(|e| e.suffix != entry.suffix);
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Finds the decompressor registered for `file`, preferring the longest
+    /// matching suffix so `.tar.gz` wins over `.gz`.
+    pub fn resolve(&self, file: &str) -> Option<&Decompressor> {
+        let lower = file.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|entry| lower.ends_with(&entry.suffix.to_lowercase()))
+            .max_by_key(|entry| entry.suffix.len())
+    }
+
+    /// Returns all registered suffixes, used to build the archive glob.
+    pub fn suffixes(&self) -> Vec<&str> {
+        self.entries.iter().map(|e| e.suffix.as_str()).collect()
+    }
+}
+
+/// Runs `command`, streaming its stdout/stderr concurrently rather than
+/// buffering until exit, and translating a missing binary into a
+/// descriptive error instead of letting callers `.unwrap()` on a spawn
+/// failure.
+pub fn spawn_reporting_missing_tool(command: Command, program: &str, verbose: bool) -> io::Result<StreamedOutput> {
+    process::run_streaming(command, verbose).map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("required tool '{}' not found on PATH", program),
+            )
+        } else {
+            e
+        }
+    })
+}
@@ -8,22 +8,32 @@
  * SPDX-License-Identifier: EPL-2.0
  *******************************************************************************/
 use std::env;
+use std::fmt::Write as FmtWrite;
 use std::fs;
 use std::fs::OpenOptions;
 use std::io::{Read, Write, Seek, SeekFrom};
-use std::process::{Command, Output};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
 
 #[cfg(target_os = "linux")]
 use std::os::unix::fs::PermissionsExt;
 
-use regex;
 use glob::glob;
-use clap::Parser;
-
-//mod manifest;
-
-const ARCHIVE_PROGRAM_CMD: &str = "7z";
+use clap::{CommandFactory, FromArgMatches, Parser};
+
+mod decompress;
+mod manifest;
+mod matchlist;
+mod native;
+mod process;
+mod report;
+use decompress::{spawn_reporting_missing_tool, DecompressorRegistry};
+use manifest::ManifestFormat;
+use matchlist::{MatchDefault, MatchList, Rule};
+use process::StreamedOutput;
+use report::{Failure, OnError, Report};
 
 /// Inverts all bits in a file after opening for read/write.
 /// This method fails if the file cannot be opened for writing.
@@ -85,141 +95,175 @@ fn try_to_change_perms_and_invert(
     Ok(())
 }
 
-/// Extracts a file from an archive using the 7z program.
-/// This method returns the output of the command regardless of success.
+/// Extracts a file from an archive using the decompressor registered for
+/// its extension.
+/// Returns `Err` if the command itself fails or exits unsuccessfully (e.g.
+/// the wrong password); failures changing permissions afterward (or a
+/// missing extracted path) don't fail the extraction itself and are instead
+/// recorded into `failures`. Errors from inverting the bits themselves are
+/// printed by `try_to_change_perms_and_invert` and are not recorded.
 fn try_to_extract_file(
+    decompressor: &decompress::Decompressor,
     file: &str,
     password: &str,
     extracted_file: &str,
     output_directory: &str,
     invert_bits: bool,
-    extract_all: bool
-) -> std::io::Result<Output> {
-    let output = if extract_all {
-        if password == "" {
-            Command::new(ARCHIVE_PROGRAM_CMD)
-                .arg("x")        
-                .arg(format!("{}", file))
-                .arg(format!("-y"))
-                .arg(format!("-o{}", output_directory))
-                .output()
-        } else {
-            Command::new(ARCHIVE_PROGRAM_CMD)
-                .arg("x")        
-                .arg(format!("{}", file))
-                .arg(format!("-p{}", password))
-                .arg(format!("-y"))
-                .arg(format!("-o{}", output_directory))
-                .output()
-        }
+    extract_all: bool,
+    verbose: bool,
+    failures: &mut Vec<Failure>,
+) -> std::io::Result<StreamedOutput> {
+    let command = if extract_all {
+        decompressor.extract_all_command(file, output_directory, password)
     } else {
-        if password == "" {
-            Command::new(ARCHIVE_PROGRAM_CMD)
-                .arg("e")        
-                .arg(format!("{}", file))
-                .arg(format!("{}", extracted_file))
-                .arg(format!("-y"))
-                .arg(format!("-o{}", output_directory))
-                .output()
-        } else {
-            Command::new(ARCHIVE_PROGRAM_CMD)
-                .arg("e")        
-                .arg(format!("{}", file))
-                .arg(format!("{}", extracted_file))
-                .arg(format!("-p{}", password))
-                .arg(format!("-y"))
-                .arg(format!("-o{}", output_directory))
-                .output()
-        }    
+        decompressor.extract_one_command(file, extracted_file, output_directory, password)
     };
+    let output = spawn_reporting_missing_tool(command, &decompressor.program, verbose)?;
+    if !output.success() {
+        return Err(std::io::Error::new(std::io::ErrorKind::Other, output.stderr));
+    }
 
     if invert_bits {
-        if extract_all {        
+        if extract_all {
             let pattern = format!("{}/**", output_directory);
             let entries = glob(pattern.as_str()).expect("Failed to read glob pattern");
 
             // Use the glob function to iterate over the matching files recursively
             for entry in entries {
-                match try_to_change_perms_and_invert(&entry.unwrap()) {
-                    Ok(_) => {},
+                let entry = match entry {
+                    Ok(entry) => entry,
                     Err(e) => {
-                        eprintln!("Error changing permissions and inverting bits: {:?}", e);
+                        failures.push(Failure {
+                            archive: file.to_string(),
+                            member: None,
+                            error: format!("reading extracted output: {:?}", e),
+                        });
+                        continue;
                     }
+                };
+                if let Err(e) = try_to_change_perms_and_invert(&entry) {
+                    failures.push(Failure {
+                        archive: file.to_string(),
+                        member: entry.to_str().map(|s| s.to_string()),
+                        error: format!("changing permissions and inverting bits: {:?}", e),
+                    });
                 }
             }
         } else {
             let mut path = PathBuf::from(output_directory);
             path.push(extracted_file);
 
-            match try_to_change_perms_and_invert(&path) {
-                Ok(_) => {},
-                Err(e) => {
-                    eprintln!("Error changing permissions and inverting bits: {:?}", e);
-                }
+            if let Err(e) = try_to_change_perms_and_invert(&path) {
+                failures.push(Failure {
+                    archive: file.to_string(),
+                    member: Some(extracted_file.to_string()),
+                    error: format!("changing permissions and inverting bits: {:?}", e),
+                });
             }
         }
     }
-    output
+    Ok(output)
 }
 
-/// Lists all files in an archive using the 7z program.
-/// This method returns the output of the command regardless of success.
+/// Lists all files in an archive using the decompressor registered for its
+/// extension.
+/// Returns `Err` if the command itself fails to spawn or exits
+/// unsuccessfully, so a listing failure (e.g. the wrong password) surfaces
+/// to the caller's failure report instead of being silently swallowed.
 fn try_to_list_files(
+    decompressor: &decompress::Decompressor,
     file: &str,
-    password: &str
-) -> std::io::Result<Output> {
-    if password == "" {
-        return Command::new(ARCHIVE_PROGRAM_CMD)
-            .arg("l")
-            .arg("-r")
-            .arg("-ba")
-            .arg(format!("{}", file))
-            .output();
-    } else {
-        //This is designed for 7zip version 23.01 x64 (Linux)
-        //The -ba switch isn't listed in the help output, but is
-        //required to suppress other verbose log messages.
-        return Command::new(ARCHIVE_PROGRAM_CMD)
-            .arg("l")
-            .arg("-r")
-            .arg("-ba")
-            .arg(format!("-p{}", password))
-            .arg(format!("{}", file))
-            .output();
+    password: &str,
+    verbose: bool,
+) -> std::io::Result<StreamedOutput> {
+    //The built-in `.7z` listing template is designed for 7zip version
+    //23.01 x64 (Linux); its -ba switch isn't listed in the help output,
+    //but is required to suppress other verbose log messages.
+    let output = spawn_reporting_missing_tool(decompressor.list_command(file, password), &decompressor.program, verbose)?;
+    if !output.success() {
+        return Err(std::io::Error::new(std::io::ErrorKind::Other, output.stderr));
     }
+    Ok(output)
 }
 
-/// Helper function to tokenize the output of a command.
-fn try_to_tokenize_lines(output: Output) -> Vec<String> {
+/// Helper function to tokenize the output of a command whose decompressor
+/// has no structured listing form (see [`try_to_list_file_names`]).
+///
+/// `bare` distinguishes two listing shapes: when true, each non-empty line
+/// already *is* the member name (`tar tzf`, `unzip -Z1`, `unrar lb` and
+/// custom `--decompressor` entries all list this way) and is used as-is
+/// besides stripping quotes; when false, it's 7z's default columned
+/// listing, so the fixed 53-byte prefix tuned for its layout is stripped —
+/// guarded by a length check so a shorter-than-expected line is skipped
+/// instead of panicking.
+fn try_to_tokenize_lines(output: StreamedOutput, bare: bool) -> Vec<String> {
     let mut output_lines = Vec::new();
 
     // Check if the command was successful
-    if output.status.success() {
-        // Convert the output to a string
-        let stdout = String::from_utf8_lossy(&output.stdout);
-
+    if output.success() {
         // Split the output into lines and tokenize each line
-        for line in stdout.lines() {        
-            let slice = line[53..].replace("\"","").to_string();
-            output_lines.push(format!("{}", slice));
+        for line in &output.stdout_lines {
+            let slice = if bare {
+                line.as_str()
+            } else if line.len() > 53 {
+                &line[53..]
+            } else {
+                continue;
+            };
+            let token = slice.trim().replace("\"", "");
+            if !token.is_empty() {
+                output_lines.push(token);
+            }
         }
     } else {
-        eprintln!(
-            "Command failed with error: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
+        eprintln!("Command failed with error: {}", output.stderr);
     }
 
     output_lines
 }
 
+/// Lists the member names of an archive, for matching against
+/// `--include`/`--exclude` and for extraction.
+///
+/// Prefers the decompressor's structured `-slt` listing (the same parsing
+/// `--manifest` uses, see [`manifest::parse_slt_listing`]) when it has one,
+/// since that's immune to the column layout shifting between versions of the
+/// underlying tool; falls back to [`try_to_tokenize_lines`] for decompressors
+/// with no structured form to fall back to.
+fn try_to_list_file_names(
+    decompressor: &decompress::Decompressor,
+    file: &str,
+    password: &str,
+    verbose: bool,
+) -> std::io::Result<Vec<String>> {
+    if let Some(command) = decompressor.list_structured_command(file, password) {
+        let output = spawn_reporting_missing_tool(command, &decompressor.program, verbose)?;
+        if !output.success() {
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, output.stderr));
+        }
+        return Ok(manifest::parse_slt_listing(file, &output.stdout_lines)
+            .into_iter()
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| entry.path)
+            .collect());
+    }
+
+    let listing = try_to_list_files(decompressor, file, password, verbose)?;
+    Ok(try_to_tokenize_lines(listing, decompressor.list_is_bare))
+}
+
 #[derive(Parser, Debug)]
 struct Args {
     #[arg(required = true, default_value = ".")]
     directory: String,
 
-    #[arg(short, long, help = "Sets the manifest file to generate.")]
-    manifest: bool,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        help = "Prints a manifest (path, size, modified, CRC32) of every archive entry in the given format instead of extracting."
+    )]
+    manifest: Option<ManifestFormat>,
 
     #[arg(short, long, help = "Extracts all files from the archive.")]
     all: bool,
@@ -236,26 +280,340 @@ struct Args {
     #[arg(short, long, default_value = ".", help = "Sets the output directory.")]
     output: String,
 
-    #[arg(short, long, default_value = ".*", help = "Sets the regular expression to match files.")]
-    regex: String,
-
-    #[arg(required = false, short, long, help = "Sets the file name term to match files.")]
-    term: String,
+    #[arg(
+        long,
+        action = clap::ArgAction::Append,
+        help = "Includes entries matching this pattern; evaluated in order with --exclude, last match wins. Glob by default, prefix with \"regex:\" or anchor with \"basename:\"."
+    )]
+    include: Vec<String>,
+
+    #[arg(
+        long,
+        action = clap::ArgAction::Append,
+        help = "Excludes entries matching this pattern; evaluated in order with --include, last match wins."
+    )]
+    exclude: Vec<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = MatchDefault::All,
+        help = "What to do with entries no --include/--exclude rule matched."
+    )]
+    match_default: MatchDefault,
 
     #[arg(short, long, default_value = "", help = "Use archive password.")]
     password: String,
+
+    #[arg(
+        long,
+        action = clap::ArgAction::Append,
+        help = "Registers a decompressor as \".ext:program args\", e.g. \".foo:mytool -d -o{out}\". May be repeated."
+    )]
+    decompressor: Vec<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Backend::External,
+        help = "Selects the extraction backend: spawn an external tool, or extract in-process."
+    )]
+    backend: Backend,
+
+    #[arg(
+        short,
+        long,
+        help = "Number of archives to process concurrently (default: available parallelism)."
+    )]
+    jobs: Option<usize>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OnError::Continue,
+        help = "Stop dispatching further work on the first failure, or continue and report every failure in the summary."
+    )]
+    on_error: OnError,
+}
+
+/// Which extraction backend handles archives: the existing external-process
+/// backend in [`decompress`], or the in-process backend in [`native`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum Backend {
+    External,
+    Native,
+}
+
+/// Settings shared by every worker thread processing an archive, kept in one
+/// struct so [`process_archive`]'s signature doesn't grow with each new CLI
+/// option.
+struct ProcessContext<'a> {
+    registry: &'a DecompressorRegistry,
+    match_list: &'a MatchList,
+    password: &'a str,
+    output_directory: &'a str,
+    invert_bits: bool,
+    extract_all: bool,
+    extract: bool,
+    verbose: bool,
+    backend: Backend,
+    manifest: Option<ManifestFormat>,
+    on_error: OnError,
+    abort: &'a AtomicBool,
+}
+
+/// Lists (and optionally extracts) a single archive, or, when `--manifest`
+/// is set, gathers its structured listing.
+///
+/// Runs on a worker thread under `--jobs`-driven parallelism, so the
+/// "Processing archive"/"Extracting" lines this would otherwise print
+/// directly are appended to the returned buffer instead (failures go into the
+/// returned [`Report`]); the caller flushes
+/// each archive's buffer in one `print!` so concurrent archives never
+/// interleave those lines. The one exception is `--verbose`'s live,
+/// line-by-line child-process streaming (see [`process::run_streaming`]),
+/// which still prints directly and so may interleave across archives run in
+/// parallel.
+fn process_archive(path: &Path, ctx: &ProcessContext) -> (String, Vec<manifest::ManifestEntry>, Report) {
+    let mut out = String::new();
+    let mut manifest_entries = Vec::new();
+    let mut report = Report::default();
+    let archive = path.display().to_string();
+
+    // `-slt` structured listing is an external-7z-only capability, so a
+    // requested manifest always goes through the decompressor registry
+    // below even when `--backend native` is selected for extraction.
+    if ctx.backend == Backend::Native && ctx.manifest.is_none() {
+        match native::Archive::open(path) {
+            Some(native_archive) => {
+                report.archives_processed += 1;
+                if ctx.verbose {
+                    let _ = writeln!(out, "Processing archive (native): {}", path.display());
+                }
+
+                let entries = match native_archive.list() {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        report.failures.push(Failure {
+                            archive: archive.clone(),
+                            member: None,
+                            error: format!("{:?}", e),
+                        });
+                        if ctx.on_error == OnError::Stop {
+                            ctx.abort.store(true, Ordering::Relaxed);
+                        }
+                        return (out, manifest_entries, report);
+                    }
+                };
+
+                // `--all` extracts the whole archive in one pass rather than
+                // per matching entry: `native_archive.extract_all` re-reads
+                // and rewrites every member each time it's called, so
+                // calling it once per matched entry turned an N-member
+                // archive into an O(n^2) re-extraction.
+                if ctx.extract && ctx.extract_all {
+                    if entries.iter().any(|entry| !entry.is_dir && ctx.match_list.matches(entry.name.as_str())) {
+                        let _ = writeln!(out, "Extracting archive: {:?}", path.display());
+                        match native_archive.extract_all(ctx.output_directory, ctx.password, ctx.invert_bits) {
+                            Ok(written) => report.members_extracted += written,
+                            Err(e) => {
+                                report.failures.push(Failure {
+                                    archive: archive.clone(),
+                                    member: None,
+                                    error: format!("{:?}", e),
+                                });
+                                if ctx.on_error == OnError::Stop {
+                                    ctx.abort.store(true, Ordering::Relaxed);
+                                }
+                            }
+                        }
+                    }
+                    return (out, manifest_entries, report);
+                }
+
+                for entry in entries {
+                    if ctx.abort.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    if entry.is_dir {
+                        continue;
+                    }
+                    let file = entry.name;
+
+                    if ctx.match_list.matches(file.as_str()) && ctx.extract {
+                        let _ = writeln!(out, "Extracting archive: {:?}, file: {}", path.display(), file);
+                        match native_archive.extract_one(file.as_str(), ctx.output_directory, ctx.password, ctx.invert_bits) {
+                            Ok(_) => report.members_extracted += 1,
+                            Err(e) => {
+                                report.failures.push(Failure {
+                                    archive: archive.clone(),
+                                    member: Some(file.clone()),
+                                    error: format!("{:?}", e),
+                                });
+                                if ctx.on_error == OnError::Stop {
+                                    ctx.abort.store(true, Ordering::Relaxed);
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+                return (out, manifest_entries, report);
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "Native backend does not support {}, falling back to external",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    let decompressor = match ctx.registry.resolve(path.to_string_lossy().as_ref()) {
+        Some(decompressor) => decompressor,
+        None => return (out, manifest_entries, report),
+    };
+
+    report.archives_processed += 1;
+
+    if ctx.verbose {
+        let _ = writeln!(out, "Processing archive: {}", path.display());
+    }
+
+    if ctx.manifest.is_some() {
+        match decompressor.list_structured_command(path.to_str().unwrap(), ctx.password) {
+            Some(command) => {
+                match spawn_reporting_missing_tool(command, &decompressor.program, ctx.verbose) {
+                    Ok(output) if output.success() => {
+                        manifest_entries.extend(manifest::parse_slt_listing(
+                            path.to_str().unwrap(),
+                            &output.stdout_lines,
+                        ));
+                    }
+                    Ok(output) => {
+                        report.failures.push(Failure { archive: archive.clone(), member: None, error: output.stderr });
+                        if ctx.on_error == OnError::Stop {
+                            ctx.abort.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    Err(e) => {
+                        report.failures.push(Failure { archive: archive.clone(), member: None, error: e.to_string() });
+                        if ctx.on_error == OnError::Stop {
+                            ctx.abort.store(true, Ordering::Relaxed);
+                        }
+                    }
+                }
+            }
+            None => {
+                report.failures.push(Failure {
+                    archive: archive.clone(),
+                    member: None,
+                    error: format!("structured listing not supported by {}", decompressor.program),
+                });
+                if ctx.on_error == OnError::Stop {
+                    ctx.abort.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+        return (out, manifest_entries, report);
+    }
+
+    let files = match try_to_list_file_names(decompressor, path.to_str().unwrap(), ctx.password, ctx.verbose) {
+        Ok(files) => files,
+        Err(e) => {
+            report.failures.push(Failure { archive: archive.clone(), member: None, error: e.to_string() });
+            if ctx.on_error == OnError::Stop {
+                ctx.abort.store(true, Ordering::Relaxed);
+            }
+            return (out, manifest_entries, report);
+        }
+    };
+
+    for file in files {
+        if ctx.abort.load(Ordering::Relaxed) {
+            break;
+        }
+        if !ctx.match_list.matches(file.as_str()) {
+            continue;
+        }
+
+        if ctx.extract {
+            let _ = writeln!(out, "Extracting archive: {:?}, file: {}", path.display(), file);
+            let failures_before = report.failures.len();
+            let result = try_to_extract_file(
+                decompressor,
+                path.to_str().unwrap(),
+                ctx.password,
+                file.replace("\"", "").as_str(),
+                ctx.output_directory,
+                ctx.invert_bits,
+                ctx.extract_all,
+                ctx.verbose,
+                &mut report.failures,
+            );
+            match result {
+                Ok(output) => {
+                    report.members_extracted += 1;
+                    if ctx.verbose {
+                        let _ = writeln!(out, "Output: {:?}", output);
+                    }
+                    if ctx.on_error == OnError::Stop && report.failures.len() > failures_before {
+                        ctx.abort.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                }
+                Err(e) => {
+                    report.failures.push(Failure { archive: archive.clone(), member: Some(file.clone()), error: e.to_string() });
+                    if ctx.on_error == OnError::Stop {
+                        ctx.abort.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    (out, manifest_entries, report)
 }
 
 /// Main function of the program.
 /// Accepts command line options and processes the archive files as they are found.
 fn main() -> std::result::Result<(), std::io::Error> {
-    let args: Vec<String> = env::args().collect();
+    let raw_args: Vec<String> = env::args().collect();
 
-    if args.len() <= 1 {
+    if raw_args.len() <= 1 {
         return Ok(());
     }
 
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    let mut registry = DecompressorRegistry::default();
+    for spec in &args.decompressor {
+        if let Err(e) = registry.register(spec) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    // Build the include/exclude match list, preserving the interleaved
+    // order the rules were given on the command line so "last match wins"
+    // means what the user typed, not "all excludes after all includes".
+    let mut match_list = MatchList::new(args.match_default);
+    let mut ordered_rules: Vec<(usize, bool, &String)> = Vec::new();
+    if let Some(indices) = matches.indices_of("include") {
+        ordered_rules.extend(indices.zip(args.include.iter()).map(|(i, v)| (i, true, v)));
+    }
+    if let Some(indices) = matches.indices_of("exclude") {
+        ordered_rules.extend(indices.zip(args.exclude.iter()).map(|(i, v)| (i, false, v)));
+    }
+    ordered_rules.sort_by_key(|(index, _, _)| *index);
+    for (_, include, spec) in ordered_rules {
+        match Rule::parse(spec, include) {
+            Ok(rule) => match_list.push(rule),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
 
     let password = args.password;
     let directory = args.directory;
@@ -263,9 +621,10 @@ fn main() -> std::result::Result<(), std::io::Error> {
     let invert_bits = args.invert;
     let extract_all = args.all;
 
-    // Define the pattern to match files recursively
+    // Define the pattern to match files recursively across every registered
+    // archive extension; entries are filtered to registry hits below.
     let mut pattern = format!(
-        "{}/**/*.7z",
+        "{}/**/*",
         directory
     );
 
@@ -280,73 +639,82 @@ fn main() -> std::result::Result<(), std::io::Error> {
         }
     }
 
-    let extract = args.extract;    
-    let entries = glob(pattern.as_str()).expect("Failed to read glob pattern");
+    let extract = args.extract;
 
-    // Use the glob function to iterate over the matching files recursively
-    for entry in entries
-    {
+    // Materialize the glob first: archives are processed by a pool of
+    // worker threads below, so every path needs to be in hand (and its
+    // `Result` resolved) before the work can be handed out.
+    let mut paths = Vec::new();
+    for entry in glob(pattern.as_str()).expect("Failed to read glob pattern") {
         match entry {
-            Ok(path) => {
-                if args.verbose {
-                    println!("Processing archive: {}", path.display());
-                }
-                
-                let files = try_to_tokenize_lines(
-                    try_to_list_files(
-                        path.to_str().unwrap(),
-                        password.as_str()
-                    ).unwrap()
-                );
+            Ok(path) => paths.push(path),
+            Err(e) => println!("Error: {:?}", e),
+        }
+    }
 
-                for file in files {
-                    if args.term != "" {
-                        if !regex::Regex::new(
-                            format!(".*{}.*", args.term).as_str()
-                        ).unwrap().is_match(format!("{}", file).as_str())
-                        {
-                            continue;
-                        } else {                            
-                            if extract {
-                                println!("Extracting archive: {:?}, file: {}", path.display(), file);
-                                let output = try_to_extract_file(
-                                    path.to_str().unwrap(),
-                                    password.as_str(),
-                                    file.replace("\"","").as_str(),
-                                    output_directory.as_str(),
-                                    invert_bits,
-                                    extract_all
-                                ).unwrap();
-                                if args.verbose {
-                                    println!("Output: {:?}", output);
-                                }
-                            }
-                        }                        
-                    } else {
-                        if regex::Regex::new(
-                                args.regex.as_str()
-                            ).unwrap().is_match(format!("{}", file).as_str())
-                        {                            
-                            if extract {
-                                println!("Extracting archive: {:?}, file: {}", path.display(), file);
-                                let output = try_to_extract_file(
-                                    path.to_str().unwrap(),
-                                    password.as_str(),
-                                    file.replace("\"","").as_str(),
-                                    output_directory.as_str(),
-                                    invert_bits,
-                                    extract_all
-                                ).unwrap();
-                                if args.verbose {
-                                    println!("Output: {:?}", output);
-                                }
-                            }                        
-                        }
-                    }
+    let abort = AtomicBool::new(false);
+    let ctx = ProcessContext {
+        registry: &registry,
+        match_list: &match_list,
+        password: password.as_str(),
+        output_directory: output_directory.as_str(),
+        invert_bits,
+        extract_all,
+        extract,
+        verbose: args.verbose,
+        backend: args.backend,
+        manifest: args.manifest,
+        on_error: args.on_error,
+        abort: &abort,
+    };
+
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+        .max(1);
+
+    // Distinct archives are independent, so they're processed across a
+    // worker pool rather than one at a time; each archive's progress lines
+    // are buffered and flushed together so parallel runs don't interleave,
+    // and (outside `--on-error stop`) one archive's errors don't stop the
+    // others from being processed.
+    let work = Mutex::new(paths.into_iter());
+    let manifest_entries = Mutex::new(Vec::new());
+    let report = Mutex::new(Report::default());
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                if ctx.abort.load(Ordering::Relaxed) {
+                    break;
                 }
-            },
-            Err(e) => println!("Error: {:?}", e),
+                let path = match work.lock().unwrap().next() {
+                    Some(path) => path,
+                    None => break,
+                };
+                let (buffer, entries, archive_report) = process_archive(&path, &ctx);
+                if !buffer.is_empty() {
+                    print!("{}", buffer);
+                }
+                if !entries.is_empty() {
+                    manifest_entries.lock().unwrap().extend(entries);
+                }
+                report.lock().unwrap().merge(archive_report);
+            });
         }
+    });
+
+    if let Some(format) = args.manifest {
+        print!("{}", manifest::render(&manifest_entries.into_inner().unwrap(), format));
+    }
+
+    let report = report.into_inner().unwrap();
+    report.print_summary();
+
+    if args.on_error == OnError::Stop && !report.failures.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("{} failure(s) occurred", report.failures.len()),
+        ));
     }
 
     Ok(())
@@ -0,0 +1,154 @@
+/*******************************************************************************
+ * Copyright (c) 2024 Nicholas LaRoche <nicholas.louis.laroche@outlook.com>
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Eclipse Public License v. 2.0 which is available at
+ * http://www.eclipse.org/legal/epl-2.0.
+ *
+ * SPDX-License-Identifier: EPL-2.0
+ *******************************************************************************/
+//! Structured archive listing and manifest rendering, backed by `7z l -slt`.
+//!
+//! `7z`'s default listing is column-aligned text whose offsets shift
+//! between versions, which is why the old tokenizer sliced a fixed
+//! `line[53..]` and broke on anything but the exact version it was tuned
+//! against. `-slt` instead emits one `key = value` block per entry,
+//! separated by blank lines, which parses unambiguously regardless of
+//! column widths. This module parses those blocks into [`ManifestEntry`]
+//! records and renders the collection `--manifest` asks for as JSON or CSV.
+
+use std::fmt::Write as _;
+
+/// A single archive member as reported by `7z l -slt`.
+#[derive(Debug, Clone)]
+pub struct ManifestEntry {
+    pub archive: String,
+    pub path: String,
+    pub size: u64,
+    pub packed_size: u64,
+    pub modified: String,
+    pub crc32: String,
+    pub is_dir: bool,
+}
+
+/// Parses the `key = value` blocks emitted by `7z l -slt` into structured
+/// entries, attributing each to `archive`.
+///
+/// The listing opens with an archive-summary block (no `Path` key) before
+/// the per-entry blocks start; blocks without a `Path` are skipped rather
+/// than treated as entries.
+pub fn parse_slt_listing(archive: &str, stdout_lines: &[String]) -> Vec<ManifestEntry> {
+    let mut entries = Vec::new();
+    let mut block: Vec<(String, String)> = Vec::new();
+
+    for line in stdout_lines {
+        if line.trim().is_empty() {
+            if let Some(entry) = entry_from_block(archive, &block) {
+                entries.push(entry);
+            }
+            block.clear();
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            block.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    if let Some(entry) = entry_from_block(archive, &block) {
+        entries.push(entry);
+    }
+
+    entries
+}
+
+fn entry_from_block(archive: &str, block: &[(String, String)]) -> Option<ManifestEntry> {
+    let get = |key: &str| block.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+
+    let path = get("Path")?;
+    let size = get("Size").and_then(|v| v.parse().ok()).unwrap_or(0);
+    let packed_size = get("Packed Size").and_then(|v| v.parse().ok()).unwrap_or(0);
+    let modified = get("Modified").unwrap_or_default();
+    let crc32 = get("CRC").unwrap_or_default();
+    let is_dir = get("Attributes").map(|a| a.contains('D')).unwrap_or(false);
+
+    Some(ManifestEntry { archive: archive.to_string(), path, size, packed_size, modified, crc32, is_dir })
+}
+
+/// Output format for `--manifest`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ManifestFormat {
+    Json,
+    Csv,
+}
+
+/// Renders `entries` as either a JSON array or a CSV table, per `format`.
+pub fn render(entries: &[ManifestEntry], format: ManifestFormat) -> String {
+    match format {
+        ManifestFormat::Json => render_json(entries),
+        ManifestFormat::Csv => render_csv(entries),
+    }
+}
+
+fn render_json(entries: &[ManifestEntry]) -> String {
+    let mut out = String::from("[\n");
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            out.push_str(",\n");
+        }
+        write!(
+            out,
+            "  {{\"archive\": {}, \"path\": {}, \"size\": {}, \"packed_size\": {}, \"modified\": {}, \"crc32\": {}, \"is_dir\": {}}}",
+            json_string(&entry.archive),
+            json_string(&entry.path),
+            entry.size,
+            entry.packed_size,
+            json_string(&entry.modified),
+            json_string(&entry.crc32),
+            entry.is_dir,
+        )
+        .expect("writing to a String cannot fail");
+    }
+    out.push_str("\n]\n");
+    out
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn render_csv(entries: &[ManifestEntry]) -> String {
+    let mut out = String::from("archive,path,size,packed_size,modified,crc32,is_dir\n");
+    for entry in entries {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            csv_field(&entry.archive),
+            csv_field(&entry.path),
+            entry.size,
+            entry.packed_size,
+            csv_field(&entry.modified),
+            csv_field(&entry.crc32),
+            entry.is_dir,
+        )
+        .expect("writing to a String cannot fail");
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,131 @@
+/*******************************************************************************
+ * Copyright (c) 2024 Nicholas LaRoche <nicholas.louis.laroche@outlook.com>
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Eclipse Public License v. 2.0 which is available at
+ * http://www.eclipse.org/legal/epl-2.0.
+ *
+ * SPDX-License-Identifier: EPL-2.0
+ *******************************************************************************/
+//! Include/exclude pattern lists, replacing the single `--regex`/`--term`
+//! match that used to gate extraction.
+//!
+//! This follows pxar's pattern-matching model: `--include`/`--exclude`
+//! rules are evaluated in order against each archive entry and the last
+//! matching rule wins, falling back to a configurable default (match
+//! everything, or match nothing) when no rule applies. Each rule may be a
+//! glob or a regex, and may anchor against the full path or just the
+//! basename.
+
+use std::path::Path;
+
+use glob::Pattern as GlobPattern;
+use regex::Regex;
+
+/// What part of an entry's path a rule is matched against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    Full,
+    Basename,
+}
+
+enum Matcher {
+    Glob(GlobPattern),
+    Regex(Regex),
+}
+
+/// A single `--include`/`--exclude` rule.
+pub struct Rule {
+    include: bool,
+    anchor: Anchor,
+    matcher: Matcher,
+}
+
+impl Rule {
+    /// Parses a rule from a CLI value, e.g. `*.rs`, `regex:^src/`, or
+    /// `regex:basename:^test_`. Unprefixed values default to a glob
+    /// anchored to the full path.
+    pub fn parse(spec: &str, include: bool) -> Result<Rule, String> {
+        let mut anchor = Anchor::Full;
+        let mut is_regex = false;
+        let mut rest = spec;
+
+        loop {
+            if let Some(stripped) = rest.strip_prefix("regex:") {
+                is_regex = true;
+                rest = stripped;
+            } else if let Some(stripped) = rest.strip_prefix("glob:") {
+                is_regex = false;
+                rest = stripped;
+            } else if let Some(stripped) = rest.strip_prefix("basename:") {
+                anchor = Anchor::Basename;
+                rest = stripped;
+            } else if let Some(stripped) = rest.strip_prefix("full:") {
+                anchor = Anchor::Full;
+                rest = stripped;
+            } else {
+                break;
+            }
+        }
+
+        let matcher = if is_regex {
+            Matcher::Regex(Regex::new(rest).map_err(|e| format!("invalid regex pattern {:?}: {}", rest, e))?)
+        } else {
+            Matcher::Glob(GlobPattern::new(rest).map_err(|e| format!("invalid glob pattern {:?}: {}", rest, e))?)
+        };
+
+        Ok(Rule { include, anchor, matcher })
+    }
+
+    fn is_match(&self, path: &str) -> bool {
+        let subject = match self.anchor {
+            Anchor::Full => path,
+            Anchor::Basename => Path::new(path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(path),
+        };
+
+        match &self.matcher {
+            Matcher::Glob(pattern) => pattern.matches(subject),
+            Matcher::Regex(regex) => regex.is_match(subject),
+        }
+    }
+}
+
+/// What an entry resolves to when no rule in the list matches it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum MatchDefault {
+    All,
+    None,
+}
+
+/// An ordered list of include/exclude rules, plus the default used when
+/// none of them apply.
+pub struct MatchList {
+    rules: Vec<Rule>,
+    default: MatchDefault,
+}
+
+impl MatchList {
+    pub fn new(default: MatchDefault) -> Self {
+        MatchList { rules: Vec::new(), default }
+    }
+
+    pub fn push(&mut self, rule: Rule) {
+        self.rules.push(rule);
+    }
+
+    /// Evaluates every rule against `path` in order; the last rule that
+    /// matches decides the outcome, and the configured default applies when
+    /// no rule matches at all.
+    pub fn matches(&self, path: &str) -> bool {
+        let mut result = matches!(self.default, MatchDefault::All);
+        for rule in &self.rules {
+            if rule.is_match(path) {
+                result = rule.include;
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,99 @@
+/*******************************************************************************
+ * Copyright (c) 2024 Nicholas LaRoche <nicholas.louis.laroche@outlook.com>
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Eclipse Public License v. 2.0 which is available at
+ * http://www.eclipse.org/legal/epl-2.0.
+ *
+ * SPDX-License-Identifier: EPL-2.0
+ *******************************************************************************/
+//! Process execution helpers that stream a child's stdout and stderr
+//! concurrently instead of buffering everything until exit.
+//!
+//! `Command::output()` only returns once the child exits, having buffered
+//! both pipes in memory; a 7z invocation writing heavily to stderr while
+//! stdout fills up can stall before the child ever gets to drain it. We
+//! instead spawn with piped stdout/stderr and drain both concurrently (a
+//! dedicated thread for stderr while the caller's thread reads stdout line
+//! by line), the same fix ripgrep made to stop its own subprocesses
+//! deadlocking.
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+/// The result of running a child process with streamed output.
+#[derive(Debug)]
+pub struct StreamedOutput {
+    pub status: ExitStatus,
+    pub stdout_lines: Vec<String>,
+    pub stderr: String,
+}
+
+impl StreamedOutput {
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+}
+
+/// Spawns `command` with piped stdout/stderr and drains both concurrently
+/// so the child is never blocked writing to either pipe.
+///
+/// When `verbose` is set, each stdout line is printed as it arrives rather
+/// than only once the command has finished.
+pub fn run_streaming(mut command: Command, verbose: bool) -> io::Result<StreamedOutput> {
+    let mut child = command.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+
+    let stderr = child.stderr.take().expect("stderr was piped");
+    let stderr_thread = thread::spawn(move || -> io::Result<String> {
+        let mut collected = String::new();
+        for line in read_lossy_lines(stderr, |_| {})? {
+            collected.push_str(&line);
+            collected.push('\n');
+        }
+        Ok(collected)
+    });
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let stdout_lines = read_lossy_lines(stdout, |line| {
+        if verbose {
+            println!("{}", line);
+        }
+    })?;
+
+    let status = child.wait()?;
+    let stderr = stderr_thread.join().unwrap_or_else(|_| Ok(String::new()))?;
+
+    Ok(StreamedOutput { status, stdout_lines, stderr })
+}
+
+/// Reads `reader` as lines split on `\n`, lossy-decoding each line instead of
+/// failing on non-UTF-8 bytes the way `BufRead::lines()` does. `on_line` is
+/// called with each line as soon as it has been read.
+///
+/// Archive listings routinely contain non-UTF-8 filename bytes, and this is
+/// the same tradeoff `String::from_utf8_lossy` made before streaming output
+/// was line-buffered; a malformed name should become a lossily-decoded
+/// string, not an `InvalidData` error that fails the whole command.
+fn read_lossy_lines<R: Read>(reader: R, mut on_line: impl FnMut(&str)) -> io::Result<Vec<String>> {
+    let mut reader = BufReader::new(reader);
+    let mut lines = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let read = reader.read_until(b'\n', &mut buf)?;
+        if read == 0 {
+            break;
+        }
+        if buf.last() == Some(&b'\n') {
+            buf.pop();
+            if buf.last() == Some(&b'\r') {
+                buf.pop();
+            }
+        }
+        let line = String::from_utf8_lossy(&buf).into_owned();
+        on_line(&line);
+        lines.push(line);
+    }
+    Ok(lines)
+}
@@ -0,0 +1,68 @@
+/*******************************************************************************
+ * Copyright (c) 2024 Nicholas LaRoche <nicholas.louis.laroche@outlook.com>
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Eclipse Public License v. 2.0 which is available at
+ * http://www.eclipse.org/legal/epl-2.0.
+ *
+ * SPDX-License-Identifier: EPL-2.0
+ *******************************************************************************/
+//! Structured failure collection, replacing the scattered `.unwrap()`s that
+//! used to abort the whole run on one bad archive or member.
+//!
+//! Borrows pxar's `on_error` callback idea: every archive/member failure is
+//! recorded as a [`Failure`] in a [`Report`] rather than panicking or being
+//! printed ad hoc, and `--on-error` decides whether a failure should stop
+//! the walk (`Stop`) or just be counted and reported at the end
+//! (`Continue`, the default).
+
+/// A single recorded failure: which archive (and, if applicable, which
+/// member of it) failed, and why.
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub archive: String,
+    pub member: Option<String>,
+    pub error: String,
+}
+
+/// What to do when an archive or member fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnError {
+    Stop,
+    Continue,
+}
+
+/// Outcome counts and failures accumulated across every archive processed,
+/// printed as a single summary once the run finishes.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub archives_processed: usize,
+    pub members_extracted: usize,
+    pub failures: Vec<Failure>,
+}
+
+impl Report {
+    pub fn merge(&mut self, other: Report) {
+        self.archives_processed += other.archives_processed;
+        self.members_extracted += other.members_extracted;
+        self.failures.extend(other.failures);
+    }
+
+    /// Prints `N archives processed, M members extracted, K failures`
+    /// followed by one line per failure. Printed to stderr, not stdout, so
+    /// it never corrupts `--manifest`'s JSON/CSV stdout output.
+    pub fn print_summary(&self) {
+        eprintln!(
+            "{} archive(s) processed, {} member(s) extracted, {} failure(s)",
+            self.archives_processed,
+            self.members_extracted,
+            self.failures.len()
+        );
+        for failure in &self.failures {
+            match &failure.member {
+                Some(member) => eprintln!("  {} [{}]: {}", failure.archive, member, failure.error),
+                None => eprintln!("  {}: {}", failure.archive, failure.error),
+            }
+        }
+    }
+}